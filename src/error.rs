@@ -36,59 +36,350 @@
 //! ```
 
 use std::collections::HashMap;
+use std::fmt;
 use std::io;
+use std::panic::Location;
 
+use http::StatusCode;
 use thiserror::Error;
 
+use crate::http_util::ErrorResponse;
 use crate::ops::Operation;
 
+/// Write `source`'s cause chain, one `Caused by: ` line per layer.
+///
+/// [`ObjectError`] and [`BackendError`] layers in the chain print with their
+/// captured `file:line`; anything else falls back to its `Display`. This
+/// gives stripped release binaries a readable "error backtrace" without a
+/// real backtrace or extra dependencies.
+fn write_cause_chain(f: &mut fmt::Formatter<'_>, source: &anyhow::Error) -> fmt::Result {
+    for cause in source.chain() {
+        if let Some(err) = cause.downcast_ref::<ObjectError>() {
+            writeln!(
+                f,
+                "Caused by: {}:{}: object error (op: {}, path: {})",
+                err.location.file(),
+                err.location.line(),
+                err.op,
+                err.path
+            )?;
+        } else if let Some(err) = cause.downcast_ref::<BackendError>() {
+            writeln!(
+                f,
+                "Caused by: {}:{}: backend error (context: {:?})",
+                err.location.file(),
+                err.location.line(),
+                err.context
+            )?;
+        } else {
+            writeln!(f, "Caused by: {cause}")?;
+        }
+    }
+    Ok(())
+}
+
+/// ErrorCode carries the high level classification of an [`Error`].
+///
+/// This is modeled after gRPC's status codes: callers should match on
+/// `code()` instead of string-matching messages or downcasting into a
+/// specific backend error type.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum ErrorCode {
+    /// Requested object or path doesn't exist.
+    NotFound,
+    /// The caller doesn't have permission to execute the specified operation.
+    PermissionDenied,
+    /// The operation is not supported by the underlying service.
+    Unsupported,
+    /// The caller has exceeded the allowed request rate.
+    RateLimited,
+    /// The underlying service is currently unavailable.
+    Unavailable,
+    /// The request is malformed or carries invalid arguments.
+    InvalidInput,
+    /// Any error that doesn't fit the other variants.
+    Unexpected,
+}
+
+impl fmt::Display for ErrorCode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let s = match self {
+            ErrorCode::NotFound => "NotFound",
+            ErrorCode::PermissionDenied => "PermissionDenied",
+            ErrorCode::Unsupported => "Unsupported",
+            ErrorCode::RateLimited => "RateLimited",
+            ErrorCode::Unavailable => "Unavailable",
+            ErrorCode::InvalidInput => "InvalidInput",
+            ErrorCode::Unexpected => "Unexpected",
+        };
+        write!(f, "{s}")
+    }
+}
+
+impl ErrorCode {
+    /// Return whether errors of this code are expected to be transient and
+    /// thus worth retrying, absent more specific service knowledge.
+    pub fn is_temporary(&self) -> bool {
+        matches!(self, ErrorCode::RateLimited | ErrorCode::Unavailable)
+    }
+}
+
+impl From<ErrorCode> for io::ErrorKind {
+    fn from(code: ErrorCode) -> Self {
+        match code {
+            ErrorCode::NotFound => io::ErrorKind::NotFound,
+            ErrorCode::PermissionDenied => io::ErrorKind::PermissionDenied,
+            ErrorCode::Unsupported => io::ErrorKind::Unsupported,
+            ErrorCode::InvalidInput => io::ErrorKind::InvalidInput,
+            ErrorCode::RateLimited | ErrorCode::Unavailable => io::ErrorKind::Interrupted,
+            ErrorCode::Unexpected => io::ErrorKind::Other,
+        }
+    }
+}
+
+/// Error is the first-class error type returned by OpenDAL operations.
+///
+/// It carries a [`ErrorCode`] that callers can match on directly, a
+/// human-readable message, and the operation/path that [`ObjectError`]
+/// also carries. An [`Error`] can
+/// always be turned into a [`std::io::Error`] via [`From`], so existing
+/// consumers that only know how to handle `io::Error` keep working
+/// unchanged.
+#[derive(Error)]
+#[error("{code}: {message} (operation: {operation:?}, path: {path:?})")]
+pub struct Error {
+    code: ErrorCode,
+    message: String,
+    operation: Option<Operation>,
+    path: Option<String>,
+    retryable: bool,
+    #[source]
+    source: Option<anyhow::Error>,
+}
+
+impl Error {
+    /// Create a new `Error` with the given code and message.
+    ///
+    /// `retryable` defaults to whatever [`ErrorCode::is_temporary`] reports
+    /// for `code`, and can be overridden with [`Error::with_retryable`] when
+    /// a service has more specific knowledge (e.g. a `Retry-After` header).
+    pub fn new(code: ErrorCode, message: impl Into<String>) -> Self {
+        Error {
+            code,
+            message: message.into(),
+            operation: None,
+            path: None,
+            retryable: code.is_temporary(),
+            source: None,
+        }
+    }
+
+    /// Attach the operation that produced this error.
+    pub fn with_operation(mut self, operation: Operation) -> Self {
+        self.operation = Some(operation);
+        self
+    }
+
+    /// Attach the path that produced this error.
+    pub fn with_path(mut self, path: impl Into<String>) -> Self {
+        self.path = Some(path.into());
+        self
+    }
+
+    /// Attach the underlying source error.
+    pub fn with_source(mut self, source: impl Into<anyhow::Error>) -> Self {
+        self.source = Some(source.into());
+        self
+    }
+
+    /// Override whether this error should be considered retryable.
+    ///
+    /// Use this when the originating status carries more information than
+    /// `code` alone, for example a 500 with a `Retry-After` header that a
+    /// service wants to surface as retryable even though its code
+    /// ([`ErrorCode::Unexpected`]) is not retryable by default.
+    pub fn with_retryable(mut self, retryable: bool) -> Self {
+        self.retryable = retryable;
+        self
+    }
+
+    /// Return the [`ErrorCode`] classifying this error.
+    pub fn code(&self) -> ErrorCode {
+        self.code
+    }
+
+    /// Return whether this error is transient, based solely on `code`.
+    ///
+    /// Analogous to treating gRPC's `Unavailable`/`ResourceExhausted` as
+    /// transient while `InvalidArgument`/`NotFound` are terminal.
+    pub fn is_temporary(&self) -> bool {
+        self.code.is_temporary()
+    }
+
+    /// Return whether a retry layer should retry the operation that
+    /// produced this error.
+    ///
+    /// Defaults to [`Error::is_temporary`] but can be overridden per
+    /// instance via [`Error::with_retryable`].
+    pub fn is_retryable(&self) -> bool {
+        self.retryable
+    }
+}
+
+impl fmt::Debug for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(
+            f,
+            "{}: {} (operation: {:?}, path: {:?})",
+            self.code, self.message, self.operation, self.path
+        )?;
+        match &self.source {
+            Some(source) => write_cause_chain(f, source),
+            None => Ok(()),
+        }
+    }
+}
+
+impl From<Error> for io::Error {
+    fn from(err: Error) -> Self {
+        let kind = io::ErrorKind::from(err.code);
+        io::Error::new(kind, err)
+    }
+}
+
+/// The HTTP-status-to-[`ErrorCode`] mapping shared by most services.
+///
+/// Exposed standalone (not just through [`ResponseErrorParser`]'s default
+/// method) so a service that overrides [`ResponseErrorParser::parse_error`]
+/// entirely, e.g. to special-case a single status for body parsing, can
+/// still fall back to it for everything else instead of duplicating the
+/// table.
+///
+/// Retryability isn't tracked separately: it's derived from the returned
+/// code via [`ErrorCode::is_temporary`], which is exactly what
+/// [`Error::new`] already does by default. Call [`Error::with_retryable`]
+/// on the result if a service learns something `code` alone can't capture
+/// (e.g. a `Retry-After` header on a 500, whose code isn't temporary).
+pub fn default_error_kind(status: StatusCode) -> ErrorCode {
+    match status {
+        StatusCode::NOT_FOUND => ErrorCode::NotFound,
+        StatusCode::UNAUTHORIZED | StatusCode::FORBIDDEN => ErrorCode::PermissionDenied,
+        StatusCode::BAD_REQUEST => ErrorCode::InvalidInput,
+        StatusCode::METHOD_NOT_ALLOWED => ErrorCode::Unsupported,
+        StatusCode::TOO_MANY_REQUESTS => ErrorCode::RateLimited,
+        StatusCode::BAD_GATEWAY | StatusCode::SERVICE_UNAVAILABLE | StatusCode::GATEWAY_TIMEOUT => {
+            ErrorCode::Unavailable
+        }
+        _ => ErrorCode::Unexpected,
+    }
+}
+
+/// Unifies how services turn an [`ErrorResponse`] into an [`Error`].
+///
+/// Modeled after actix-web's `ResponseError`: implement this once per
+/// service and override only the parts that need service-specific body
+/// parsing (error codes and messages returned in a JSON/XML payload, say).
+/// The default implementation covers the common HTTP-status mapping via
+/// [`default_error_kind`], so a service with nothing unusual to say can
+/// implement the trait with an empty `impl` block.
+pub trait ResponseErrorParser {
+    /// Parse `er` into the unified [`Error`] for the given operation/path.
+    ///
+    /// The returned [`Error`] carries an [`ObjectError`] as its source, so
+    /// the caller location captured there and [`write_cause_chain`] keep
+    /// working for errors built through this trait.
+    #[track_caller]
+    fn parse_error(&self, op: Operation, path: &str, er: ErrorResponse) -> Error {
+        let code = default_error_kind(er.status_code());
+        let message = format!("{er}");
+        Error::new(code, message.clone())
+            .with_operation(op)
+            .with_path(path)
+            .with_source(ObjectError::new(op, path, anyhow::anyhow!(message)))
+    }
+}
+
 /// BackendError carries backend related context.
 ///
 /// # Notes
 ///
 /// This error is used to carry context only, and should never be returned to users.
 /// Please wrap in [`std::io::Error`] instead.
-#[derive(Error, Debug)]
+#[derive(Error)]
 #[error("backend error: (context: {context:?}, source: {source})")]
 pub struct BackendError {
     context: HashMap<String, String>,
     source: anyhow::Error,
+    location: &'static Location<'static>,
 }
 
 impl BackendError {
+    #[track_caller]
     pub fn new(context: HashMap<String, String>, source: impl Into<anyhow::Error>) -> Self {
         BackendError {
             context,
             source: source.into(),
+            location: Location::caller(),
         }
     }
 }
 
+impl fmt::Debug for BackendError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(
+            f,
+            "{}:{}: backend error (context: {:?})",
+            self.location.file(),
+            self.location.line(),
+            self.context
+        )?;
+        write_cause_chain(f, &self.source)
+    }
+}
+
 /// ObjectError carries object related context.
 ///
 /// # Notes
 ///
 /// This error is used to carry context only, and should never be returned to users.
 /// Please wrap in [`std::io::Error`] with correct [`std::io::ErrorKind`] instead.
-#[derive(Error, Debug)]
+#[derive(Error)]
 #[error("object error: (op: {op}, path: {path}, source: {source})")]
 pub struct ObjectError {
     op: Operation,
     path: String,
     source: anyhow::Error,
+    location: &'static Location<'static>,
 }
 
 impl ObjectError {
+    #[track_caller]
     pub fn new(op: Operation, path: &str, source: impl Into<anyhow::Error>) -> Self {
         ObjectError {
             op,
             path: path.to_string(),
             source: source.into(),
+            location: Location::caller(),
         }
     }
 }
 
+impl fmt::Debug for ObjectError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(
+            f,
+            "{}:{}: object error (op: {}, path: {})",
+            self.location.file(),
+            self.location.line(),
+            self.op,
+            self.path
+        )?;
+        write_cause_chain(f, &self.source)
+    }
+}
+
 /// Creates new Unsupported Object Error.
+#[track_caller]
 pub fn new_unsupported_object_error(op: Operation, path: &str) -> io::Error {
     io::Error::new(
         io::ErrorKind::Unsupported,
@@ -101,6 +392,7 @@ pub fn new_unsupported_object_error(op: Operation, path: &str) -> io::Error {
 }
 
 /// Creates an error as [`ObjectError`] and wrapped with [`io::Error::other`]
+#[track_caller]
 pub fn new_other_object_error(
     op: Operation,
     path: &str,
@@ -110,9 +402,136 @@ pub fn new_other_object_error(
 }
 
 /// Creates an error as [`BackendError`] and wrapped with [`io::Error::other`]
+#[track_caller]
 pub fn new_other_backend_error(
     context: HashMap<String, String>,
     source: impl Into<anyhow::Error>,
 ) -> io::Error {
     io::Error::new(io::ErrorKind::Other, BackendError::new(context, source))
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn object_error_debug_prints_location_and_cause_chain() {
+        let inner = ObjectError::new(Operation::Read, "/inner", anyhow::anyhow!("disk full"));
+        let outer = ObjectError::new(Operation::Read, "/outer", inner);
+
+        let debug = format!("{outer:?}");
+
+        assert!(debug.contains("error.rs"), "missing location: {debug}");
+        assert!(debug.contains("object error (op: Read, path: /outer)"));
+        assert!(debug.contains("Caused by:"), "missing cause chain: {debug}");
+        assert!(debug.contains("path: /inner"), "inner location not printed: {debug}");
+    }
+
+    #[test]
+    fn object_error_debug_falls_back_to_display_for_opaque_causes() {
+        let err = ObjectError::new(Operation::Write, "/x", anyhow::anyhow!("disk full"));
+
+        let debug = format!("{err:?}");
+
+        assert!(debug.contains("Caused by: disk full"), "{debug}");
+    }
+
+    #[test]
+    fn backend_error_debug_prints_location_and_context() {
+        let mut context = HashMap::new();
+        context.insert("endpoint".to_string(), "https://example.com".to_string());
+        let err = BackendError::new(context, anyhow::anyhow!("connection refused"));
+
+        let debug = format!("{err:?}");
+
+        assert!(debug.contains("error.rs"), "missing location: {debug}");
+        assert!(debug.contains("backend error"));
+        assert!(debug.contains("endpoint"));
+        assert!(debug.contains("Caused by: connection refused"));
+    }
+
+    #[test]
+    fn error_debug_prints_its_own_cause_chain() {
+        let err = Error::new(ErrorCode::Unexpected, "boom")
+            .with_operation(Operation::Read)
+            .with_path("/x")
+            .with_source(ObjectError::new(Operation::Read, "/x", anyhow::anyhow!("disk full")));
+
+        let debug = format!("{err:?}");
+
+        assert!(debug.contains("Unexpected: boom"), "{debug}");
+        assert!(debug.contains("error.rs"), "missing location: {debug}");
+        assert!(debug.contains("Caused by:"), "missing cause chain: {debug}");
+    }
+
+    #[test]
+    fn error_debug_survives_the_io_error_bridge() {
+        let err = Error::new(ErrorCode::Unexpected, "boom")
+            .with_operation(Operation::Read)
+            .with_path("/x")
+            .with_source(ObjectError::new(Operation::Read, "/x", anyhow::anyhow!("disk full")));
+
+        let io_err: io::Error = err.into();
+        let debug = format!("{io_err:?}");
+
+        assert!(debug.contains("error.rs"), "missing location: {debug}");
+        assert!(debug.contains("Caused by: disk full"), "{debug}");
+    }
+
+    #[test]
+    fn default_error_kind_maps_common_statuses() {
+        assert_eq!(default_error_kind(StatusCode::NOT_FOUND), ErrorCode::NotFound);
+        assert_eq!(
+            default_error_kind(StatusCode::UNAUTHORIZED),
+            ErrorCode::PermissionDenied
+        );
+        assert_eq!(
+            default_error_kind(StatusCode::FORBIDDEN),
+            ErrorCode::PermissionDenied
+        );
+        assert_eq!(
+            default_error_kind(StatusCode::BAD_REQUEST),
+            ErrorCode::InvalidInput
+        );
+        assert_eq!(
+            default_error_kind(StatusCode::METHOD_NOT_ALLOWED),
+            ErrorCode::Unsupported
+        );
+        assert_eq!(
+            default_error_kind(StatusCode::TOO_MANY_REQUESTS),
+            ErrorCode::RateLimited
+        );
+        assert_eq!(
+            default_error_kind(StatusCode::BAD_GATEWAY),
+            ErrorCode::Unavailable
+        );
+        assert_eq!(
+            default_error_kind(StatusCode::SERVICE_UNAVAILABLE),
+            ErrorCode::Unavailable
+        );
+        assert_eq!(
+            default_error_kind(StatusCode::GATEWAY_TIMEOUT),
+            ErrorCode::Unavailable
+        );
+        assert_eq!(
+            default_error_kind(StatusCode::IM_A_TEAPOT),
+            ErrorCode::Unexpected
+        );
+    }
+
+    #[test]
+    fn default_error_kind_retryability_matches_code() {
+        for status in [
+            StatusCode::NOT_FOUND,
+            StatusCode::FORBIDDEN,
+            StatusCode::BAD_REQUEST,
+            StatusCode::METHOD_NOT_ALLOWED,
+            StatusCode::TOO_MANY_REQUESTS,
+            StatusCode::SERVICE_UNAVAILABLE,
+            StatusCode::IM_A_TEAPOT,
+        ] {
+            let code = default_error_kind(status);
+            assert_eq!(Error::new(code, "x").is_retryable(), code.is_temporary());
+        }
+    }
+}
@@ -12,15 +12,17 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
-use std::io::Error;
-use std::io::ErrorKind;
-use std::io::Result;
+use std::io;
 
 use anyhow::anyhow;
 use http::StatusCode;
 use serde::Deserialize;
 
+use crate::error::default_error_kind;
+use crate::error::Error;
+use crate::error::ErrorCode;
 use crate::error::ObjectError;
+use crate::error::ResponseErrorParser;
 use crate::http_util::ErrorResponse;
 use crate::ops::Operation;
 
@@ -35,7 +37,29 @@ struct IpfsError {
     ty: String,
 }
 
-/// Parse error response into io::Error.
+/// Map a Kubo RPC error message (returned in the body of a 500 response)
+/// to the [`ErrorCode`] it represents.
+///
+/// Kubo reuses the same 500 status for a whole vocabulary of errors, so the
+/// message (or a stable prefix of it) is the only thing that tells them
+/// apart. Unknown messages fall back to [`ErrorCode::Unexpected`]. Whether
+/// the code is retryable is derived later, from [`ErrorCode::is_temporary`].
+fn kind_for_message(message: &str) -> ErrorCode {
+    if message == "file does not exist"
+        || message.starts_with("no link named")
+        || message.contains("merkledag: not found")
+    {
+        ErrorCode::NotFound
+    } else if message.starts_with("invalid path") {
+        ErrorCode::InvalidInput
+    } else if message.contains("context deadline exceeded") {
+        ErrorCode::Unavailable
+    } else {
+        ErrorCode::Unexpected
+    }
+}
+
+/// Parses Kubo RPC error responses.
 ///
 /// > Status code 500 means that the function does exist, but IPFS was not
 /// > able to fulfil the request because of an error.
@@ -43,29 +67,79 @@ struct IpfsError {
 /// > usually returned with the body of the response
 /// > (if no error, check the daemon logs).
 ///
+/// Every status besides 500 uses the shared [`default_error_kind`] mapping;
 /// ref: https://docs.ipfs.tech/reference/kubo/rpc/#http-status-codes
-pub fn parse_error(op: Operation, path: &str, er: ErrorResponse) -> Error {
-    let kind = match er.status_code() {
-        StatusCode::INTERNAL_SERVER_ERROR => {
-            let ie: Result<IpfsError> = serde_json::from_slice(er.body()).map_err(|err| {
-                Error::new(
-                    ErrorKind::Other,
-                    ObjectError::new(op, path, anyhow!("deserialize error content: {err:?}")),
-                )
-            });
-            match ie {
-                Ok(ie) => match ie.message.as_str() {
-                    "file does not exist" => ErrorKind::NotFound,
-                    _ => ErrorKind::Other,
-                },
-                Err(e) => return e,
+pub(crate) struct KuboErrorParser;
+
+impl ResponseErrorParser for KuboErrorParser {
+    #[track_caller]
+    fn parse_error(&self, op: Operation, path: &str, er: ErrorResponse) -> Error {
+        let code = match er.status_code() {
+            StatusCode::INTERNAL_SERVER_ERROR => {
+                match serde_json::from_slice::<IpfsError>(er.body()) {
+                    Ok(ie) => kind_for_message(&ie.message),
+                    Err(err) => {
+                        return Error::new(
+                            ErrorCode::Unexpected,
+                            format!("deserialize error content: {err}"),
+                        )
+                        .with_operation(op)
+                        .with_path(path)
+                        .with_source(ObjectError::new(
+                            op,
+                            path,
+                            anyhow!("deserialize error content: {err:?}"),
+                        ));
+                    }
+                }
             }
-        }
-        StatusCode::BAD_GATEWAY | StatusCode::SERVICE_UNAVAILABLE | StatusCode::GATEWAY_TIMEOUT => {
-            ErrorKind::Interrupted
-        }
-        _ => ErrorKind::Other,
-    };
+            status => default_error_kind(status),
+        };
+
+        let message = format!("{er}");
+        Error::new(code, message.clone())
+            .with_operation(op)
+            .with_path(path)
+            .with_source(ObjectError::new(op, path, anyhow!(message)))
+    }
+}
+
+/// Parse error response into io::Error.
+///
+/// Kept for callers that only know how to handle [`io::Error`]; new code
+/// should prefer calling [`KuboErrorParser`] directly via
+/// [`ResponseErrorParser`] and matching on `Error::code()`.
+#[track_caller]
+pub fn parse_error(op: Operation, path: &str, er: ErrorResponse) -> io::Error {
+    KuboErrorParser.parse_error(op, path, er).into()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
 
-    Error::new(kind, ObjectError::new(op, path, anyhow!("{er}")))
+    #[test]
+    fn kind_for_message_maps_known_kubo_errors() {
+        assert_eq!(kind_for_message("file does not exist"), ErrorCode::NotFound);
+        assert_eq!(
+            kind_for_message("no link named \"foo\" under QmHash"),
+            ErrorCode::NotFound
+        );
+        assert_eq!(
+            kind_for_message("merkledag: not found"),
+            ErrorCode::NotFound
+        );
+        assert_eq!(
+            kind_for_message("invalid path \"xxx\""),
+            ErrorCode::InvalidInput
+        );
+        assert_eq!(
+            kind_for_message("context deadline exceeded"),
+            ErrorCode::Unavailable
+        );
+        assert_eq!(
+            kind_for_message("some unrecognized kubo error"),
+            ErrorCode::Unexpected
+        );
+    }
 }